@@ -0,0 +1,228 @@
+use crossbeam::utils::CachePadded;
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct Slot<T> {
+    // The lap-aware sequence number that tells a producer/consumer whether
+    // this slot is ready for them.
+    stamp: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// A fixed-capacity multi-producer multi-consumer queue.
+///
+/// This complements `SelkirkLinkedQueue`: where the linked queue is
+/// unbounded and can grow without limit, `ArrayQueue` is backed by a single
+/// pre-allocated buffer and rejects pushes once it is full, which makes it
+/// useful for backpressure.
+///
+/// <p>This implementation is based on the bounded MPMC queue described by
+/// <a href="https://www.1024cores.net/home/lock-free-algorithms/queues/bounded-mpmc-queue">
+/// Dmitry Vyukov</a>. Each slot carries its own sequence stamp so that
+/// producers and consumers can tell, without taking a lock, whether the
+/// slot they landed on is the one they are meant to fill or drain.
+pub struct ArrayQueue<T> {
+    buffer: Box<[CachePadded<Slot<T>>]>,
+    head: CachePadded<AtomicUsize>,
+    tail: CachePadded<AtomicUsize>,
+    // The smallest power of two strictly greater than `capacity`; the bits
+    // below it index into `buffer`, the bits above it count the lap.
+    one_lap: usize,
+    capacity: usize,
+}
+
+unsafe impl<T: Send> Send for ArrayQueue<T> {}
+unsafe impl<T: Send> Sync for ArrayQueue<T> {}
+
+impl<T> ArrayQueue<T> {
+    /// Creates a new, empty `ArrayQueue` that can hold at most `capacity`
+    /// elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> ArrayQueue<T> {
+        assert!(capacity > 0, "capacity must be non-zero");
+
+        let one_lap = (capacity + 1).next_power_of_two();
+        let buffer = (0..capacity)
+            .map(|i| {
+                CachePadded::new(Slot {
+                    stamp: AtomicUsize::new(i),
+                    value: UnsafeCell::new(MaybeUninit::uninit()),
+                })
+            })
+            .collect();
+
+        ArrayQueue {
+            buffer,
+            head: CachePadded::new(AtomicUsize::new(0)),
+            tail: CachePadded::new(AtomicUsize::new(0)),
+            one_lap,
+            capacity,
+        }
+    }
+
+    /// Pushes an element onto the queue, returning it back as an error if
+    /// the queue is full.
+    pub fn push(&self, value: T) -> Result<(), T> {
+        let mut tail = self.tail.load(Ordering::SeqCst);
+        loop {
+            let index = tail & (self.one_lap - 1);
+            let slot = &self.buffer[index];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+
+            if stamp == tail {
+                let new_tail = if index + 1 < self.capacity {
+                    tail + 1
+                } else {
+                    tail - index + self.one_lap
+                };
+
+                match self.tail.compare_exchange_weak(
+                    tail,
+                    new_tail,
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                ) {
+                    Ok(_) => {
+                        unsafe {
+                            (*slot.value.get()).write(value);
+                        }
+                        slot.stamp.store(tail + 1, Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(t) => tail = t,
+                }
+            } else if stamp < tail {
+                return Err(value);
+            } else {
+                tail = self.tail.load(Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Pops an element off the queue, or returns `None` if it is empty.
+    pub fn pop(&self) -> Option<T> {
+        let mut head = self.head.load(Ordering::SeqCst);
+        loop {
+            let index = head & (self.one_lap - 1);
+            let slot = &self.buffer[index];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+
+            if stamp == head + 1 {
+                let new_head = if index + 1 < self.capacity {
+                    head + 1
+                } else {
+                    head - index + self.one_lap
+                };
+
+                match self.head.compare_exchange_weak(
+                    head,
+                    new_head,
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                ) {
+                    Ok(_) => {
+                        let value = unsafe { (*slot.value.get()).as_ptr().read() };
+                        slot.stamp.store(head + self.one_lap, Ordering::Release);
+                        return Some(value);
+                    }
+                    Err(h) => head = h,
+                }
+            } else if stamp == head {
+                return None;
+            } else {
+                head = self.head.load(Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Returns the number of elements currently in the queue.
+    pub fn len(&self) -> usize {
+        loop {
+            let tail = self.tail.load(Ordering::SeqCst);
+            let head = self.head.load(Ordering::SeqCst);
+
+            // Make sure the tail didn't move while we were reading it.
+            if self.tail.load(Ordering::SeqCst) == tail {
+                let hix = head & (self.one_lap - 1);
+                let tix = tail & (self.one_lap - 1);
+
+                return if tail == head {
+                    0
+                } else if tix <= hix {
+                    self.capacity - hix + tix
+                } else {
+                    tix - hix
+                };
+            }
+        }
+    }
+
+    /// Returns `true` if the queue holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::SeqCst) == self.tail.load(Ordering::SeqCst)
+    }
+
+    /// Returns `true` if the queue is at capacity.
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity
+    }
+
+    /// Returns the capacity the queue was constructed with.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl<T> Drop for ArrayQueue<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn array_queue_respects_fifo_order() {
+        let q = ArrayQueue::new(3);
+        assert_eq!(Ok(()), q.push(1));
+        assert_eq!(Ok(()), q.push(2));
+        assert_eq!(Ok(()), q.push(3));
+        assert_eq!(Some(1), q.pop());
+        assert_eq!(Some(2), q.pop());
+        assert_eq!(Some(3), q.pop());
+        assert_eq!(None, q.pop());
+    }
+
+    #[test]
+    fn array_queue_rejects_push_when_full() {
+        let q = ArrayQueue::new(2);
+        assert_eq!(Ok(()), q.push(1));
+        assert_eq!(Ok(()), q.push(2));
+        assert_eq!(Err(3), q.push(3));
+    }
+
+    #[test]
+    fn array_queue_tracks_len_across_wraparound() {
+        let q = ArrayQueue::new(2);
+        for _ in 0..5 {
+            q.push(1).unwrap();
+            assert_eq!(1, q.len());
+            assert!(!q.is_empty());
+            assert!(!q.is_full());
+            q.pop().unwrap();
+            assert!(q.is_empty());
+        }
+    }
+
+    #[test]
+    fn array_queue_reports_capacity() {
+        let q: ArrayQueue<i32> = ArrayQueue::new(7);
+        assert_eq!(7, q.capacity());
+    }
+}
@@ -1,9 +1,14 @@
 #![allow(dead_code)]
-use crossbeam::epoch::{pin, Atomic, Guard, Owned, Shared};
+use crossbeam::epoch::{pin, Atomic, Owned};
 use crossbeam::utils::CachePadded;
-use std::fmt::{Debug, Display};
+use std::cell::UnsafeCell;
+use std::fmt::Debug;
 use std::sync::atomic::Ordering;
 
+mod array_queue;
+
+pub use array_queue::ArrayQueue;
+
 /// This queue orders elements FIFO (first-in-first-out).
 /// The <em>head</em> of the queue is that element that has been on the
 /// queue the longest time.
@@ -64,23 +69,28 @@ pub fn new_atomic_null() -> Atomic<()> {
 }
 
 pub(crate) struct Node<T> {
-    pub(crate) item: T,
+    // `None` for the dummy/sentinel node that `head` always points at, and
+    // for a node whose item has already been handed out by `dequeue`.
+    // Wrapped in `UnsafeCell` because a successful `dequeue` mutates this
+    // field through a `Shared` (i.e. shared) reference to the node; the CAS
+    // on `head` that precedes the mutation is what makes that access race
+    // free.
+    pub(crate) item: UnsafeCell<Option<T>>,
     pub(crate) next: Atomic<Node<T>>,
 }
 
-impl<T> Node<T>
-where
-    T: Eq + Sized + Default + Display + Debug,
-{
+unsafe impl<T: Send> Sync for Node<T> {}
+
+impl<T> Node<T> {
     pub(crate) fn new_empty() -> Node<T> {
         Node {
-            item: T::default(),
+            item: UnsafeCell::new(None),
             next: Atomic::null(),
         }
     }
     pub(crate) fn new(elem: T) -> Node<T> {
         Node {
-            item: elem,
+            item: UnsafeCell::new(Some(elem)),
             next: Atomic::null(),
         }
     }
@@ -90,7 +100,7 @@ pub struct SelkirkLinkedQueue<T> {
     // A node from which the first live (non-deleted) node (if any)
     // can be reached in O(1) time.
     // Invariants:
-    // - all live nodes are reachable from head via succ()
+    // - all live nodes are reachable from head by following `next` pointers
     // - head != null
     // - (tmp = head).next != tmp || tmp != head
     // Non-invariants:
@@ -102,7 +112,8 @@ pub struct SelkirkLinkedQueue<T> {
     // A node from which the last node on list (that is, the unique
     // node with node.next == null) can be reached in O(1) time.
     // Invariants:
-    // - the last node is always reachable from tail via succ()
+    // - the last node is always reachable from tail by following `next`
+    //   pointers
     // - tail != null
     // Non-invariants:
     // - tail.item may or may not be null.
@@ -112,12 +123,12 @@ pub struct SelkirkLinkedQueue<T> {
     tail: CachePadded<Atomic<Node<T>>>,
 }
 
-unsafe impl<T> Send for SelkirkLinkedQueue<T> {}
-unsafe impl<T> Sync for SelkirkLinkedQueue<T> {}
+unsafe impl<T: Send> Send for SelkirkLinkedQueue<T> {}
+unsafe impl<T: Send> Sync for SelkirkLinkedQueue<T> {}
 
 impl<T> SelkirkLinkedQueue<T>
 where
-    T: Eq + Sized + Default + Debug + Display,
+    T: Eq + Debug,
 {
     pub fn new() -> SelkirkLinkedQueue<T> {
         let head = CachePadded::new(Atomic::new(Node::<T>::new_empty()));
@@ -128,52 +139,108 @@ where
         self.offer(elem)
     }
 
-    // Returns the successor of p, or the head node if p.next has been
-    // linked to self, which will only be true if traversing with a
-    // stale pointer that is now off the list.
-    fn succ<'g>(p: Atomic<Node<T>>, guard: &'g Guard) -> Atomic<Node<T>> {
-        //        let current_node = p.load(Ordering::SeqCst, guard);
-        //        let current_item = current_node.item.load(Ordering::SeqCst,guard);
-        //        let next = p.next.load(Ordering::SeqCst, guard);
-        //        if (p == next) {}
-        return Atomic::null();
-    }
-
     fn offer(&self, elem: T) {
-        //        let guard = pin();
-        //        let new_node = Node::new(elem);
-        //        let mut p = t;
-        //        loop {
-        //            let t = &self.tail.load(Ordering::SeqCst, &guard);
-        //            let q = p.load(Ordering::SeqCst, &guard);
-        //            if q.is_null() {}
-        //        }
+        let mut new_node = Owned::new(Node::new(elem));
+        let guard = &pin();
+        loop {
+            let t = self.tail.load(Ordering::Acquire, guard);
+            let t_ref = unsafe { t.deref() };
+            let q = t_ref.next.load(Ordering::Acquire, guard);
+            if t != self.tail.load(Ordering::Acquire, guard) {
+                // tail changed in the meantime, retry
+                continue;
+            }
+            if q.is_null() {
+                // t is the last node, try to link the new node onto it
+                match t_ref
+                    .next
+                    .compare_and_set(q, new_node, Ordering::AcqRel, guard)
+                {
+                    Ok(new) => {
+                        // Enqueue is done, try to swing tail to the new node.
+                        // It is fine if this CAS fails; some other thread
+                        // will do it for us.
+                        let _ = self.tail.compare_and_set(t, new, Ordering::AcqRel, guard);
+                        return;
+                    }
+                    Err(err) => {
+                        // somebody beat us to it, reclaim our node and retry
+                        new_node = err.new;
+                    }
+                }
+            } else {
+                // tail is lagging behind, help it catch up and retry
+                let _ = self.tail.compare_and_set(t, q, Ordering::AcqRel, guard);
+            }
+        }
     }
 
     pub fn enqueue(&self, elem: T) {
-        let new_node = Node::new(elem);
-        let guard = &pin();
-        // Enqueue is done. Try to swing Tail to the inserted node
-        // CAS(&Qâ€“>Tail, tail, <node, tail.count+1>)
-        let old_tail_node = self.tail.compare_and_set(
-            self.tail.load(Ordering::SeqCst, guard),
-            Owned::new(new_node),
-            Ordering::AcqRel,
-            guard,
-        );
+        self.offer(elem)
     }
 
     pub fn print_last(&self) {
         let guard = &pin();
         if let Some(tail_node) = unsafe { self.tail.load(Ordering::SeqCst, guard).as_ref() } {
-            println!("tail_node: {}", tail_node.item)
+            let item = unsafe { &*tail_node.item.get() };
+            println!("tail_node: {:?}", item);
         } else {
-            println!("Fuck?");
+            println!("queue has no tail node");
         }
     }
 
     pub fn dequeue(&self) -> Result<T, ()> {
-        Err(())
+        let guard = &pin();
+        loop {
+            let h = self.head.load(Ordering::Acquire, guard);
+            let t = self.tail.load(Ordering::Acquire, guard);
+            let h_ref = unsafe { h.deref() };
+            let next = h_ref.next.load(Ordering::Acquire, guard);
+            if h != self.head.load(Ordering::Acquire, guard) {
+                // head changed in the meantime, retry
+                continue;
+            }
+            if h == t {
+                if next.is_null() {
+                    // queue is empty
+                    return Err(());
+                }
+                // tail is lagging behind, help it catch up and retry
+                let _ = self.tail.compare_and_set(t, next, Ordering::AcqRel, guard);
+                continue;
+            }
+            let next_ref = unsafe { next.deref() };
+            if self
+                .head
+                .compare_and_set(h, next, Ordering::AcqRel, guard)
+                .is_ok()
+            {
+                let item = unsafe { (*next_ref.item.get()).take() }
+                    .expect("a live node always holds an item");
+                unsafe {
+                    guard.defer_destroy(h);
+                }
+                return Ok(item);
+            }
+        }
+    }
+}
+
+impl<T> Drop for SelkirkLinkedQueue<T> {
+    fn drop(&mut self) {
+        // `&mut self` guarantees no other thread can be touching the queue,
+        // so we walk the list and reclaim every node (including the
+        // sentinel) directly, rather than going through the lock-free
+        // `dequeue` protocol.
+        let guard = &pin();
+        let mut current = self.head.load(Ordering::Relaxed, guard);
+        while !current.is_null() {
+            let next = unsafe { current.deref() }.next.load(Ordering::Relaxed, guard);
+            unsafe {
+                drop(current.into_owned());
+            }
+            current = next;
+        }
     }
 }
 
@@ -210,4 +277,16 @@ mod tests {
         queue.print_last();
         scope.unwrap();
     }
+
+    #[test]
+    fn dequeue_in_fifo_order() {
+        let queue = SelkirkLinkedQueue::<i32>::new();
+        for i in 0..100 {
+            queue.enqueue(i);
+        }
+        for i in 0..100 {
+            assert_eq!(Ok(i), queue.dequeue());
+        }
+        assert_eq!(Err(()), queue.dequeue());
+    }
 }
@@ -0,0 +1,285 @@
+// MIT License Mayeul (Mike) Fournial <mayeul.fournial@outlook.com> - 2017
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// A priority queue keyed by a hashable item, with an associated mutable
+/// priority. Complements the value-only `PriorityQueue` by allowing a
+/// caller to update an item's priority in `O(log n)` time instead of
+/// re-inserting a stale entry.
+///
+/// This is exactly the decrease-key operation Dijkstra's algorithm and A*
+/// need for their frontier: pushing a cheaper route to an already-queued
+/// node should update its priority in place, rather than leaving a
+/// tombstoned duplicate behind in the heap.
+///
+/// # Examples
+///
+/// ```
+/// extern crate collections_more;
+/// use collections_more::queue::indexed_priority_queue::IndexedPriorityQueue;
+/// # fn main() {
+/// let mut pq: IndexedPriorityQueue<&str, i32> = IndexedPriorityQueue::new();
+/// pq.push("a", 4);
+/// pq.push("b", 9);
+/// pq.push("c", 0);
+///
+/// assert_eq!(Some((&"b", &9)), pq.peek());
+///
+/// // decrease-key: "b" is no longer the most urgent entry
+/// pq.change_priority(&"b", 1);
+/// assert_eq!(Some((&"a", &4)), pq.peek());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct IndexedPriorityQueue<Item, Priority>
+where
+	Item: Eq + Hash + Clone + Debug,
+	Priority: PartialOrd + Debug,
+{
+	heap: Vec<(Item, Priority)>,
+	index: HashMap<Item, usize>,
+}
+
+impl<Item, Priority> IndexedPriorityQueue<Item, Priority>
+where
+	Item: Eq + Hash + Clone + Debug,
+	Priority: PartialOrd + Debug,
+{
+	/// Constructs a new, empty `IndexedPriorityQueue`.
+	#[inline]
+	pub fn new() -> IndexedPriorityQueue<Item, Priority> {
+		IndexedPriorityQueue {
+			heap: Vec::new(),
+			index: HashMap::new(),
+		}
+	}
+
+	/// Constructs a new, empty `IndexedPriorityQueue` with the specified
+	/// capacity.
+	#[inline]
+	pub fn with_capacity(capacity: usize) -> IndexedPriorityQueue<Item, Priority> {
+		IndexedPriorityQueue {
+			heap: Vec::with_capacity(capacity),
+			index: HashMap::with_capacity(capacity),
+		}
+	}
+
+	/// Inserts `item` with `priority`, or updates its priority if it is
+	/// already present.
+	pub fn push(&mut self, item: Item, priority: Priority) {
+		if let Some(&i) = self.index.get(&item) {
+			self.heap[i].1 = priority;
+			self.fix(i);
+			return;
+		}
+
+		let i = self.heap.len();
+		self.index.insert(item.clone(), i);
+		self.heap.push((item, priority));
+		self.siftup(i);
+	}
+
+	/// Updates the priority of an already-queued `item`, returning its
+	/// previous priority, or `None` if `item` is not in the queue.
+	pub fn change_priority(&mut self, item: &Item, new_priority: Priority) -> Option<Priority> {
+		let i = *self.index.get(item)?;
+		let old_priority = std::mem::replace(&mut self.heap[i].1, new_priority);
+		self.fix(i);
+		Some(old_priority)
+	}
+
+	/// Returns the current priority of `item`, or `None` if it is not in
+	/// the queue.
+	pub fn get_priority(&self, item: &Item) -> Option<&Priority> {
+		self.index.get(item).map(|&i| &self.heap[i].1)
+	}
+
+	/// Returns a borrow of the highest-priority item and its priority, in
+	/// `O(1)` time, or `None` if the queue is empty.
+	pub fn peek(&self) -> Option<(&Item, &Priority)> {
+		self.heap.first().map(|(item, priority)| (item, priority))
+	}
+
+	/// Removes and returns the highest-priority item and its priority, or
+	/// `None` if the queue is empty.
+	pub fn pop(&mut self) -> Option<(Item, Priority)> {
+		if self.heap.is_empty() {
+			return None;
+		}
+
+		let last = self.heap.len() - 1;
+		self.swap(0, last);
+		let (item, priority) = self.heap.pop().expect("queue was just checked non-empty");
+		self.index.remove(&item);
+
+		if !self.heap.is_empty() {
+			self.siftdown(0);
+		}
+
+		Some((item, priority))
+	}
+
+	/// Returns the number of items in the queue.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.heap.len()
+	}
+
+	/// Returns true if there is no item in the queue.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.heap.is_empty()
+	}
+
+	// Restores the heap property around index `i` after its priority
+	// changed, whichever direction it needs to move.
+	fn fix(&mut self, i: usize) {
+		self.siftup(i);
+		self.siftdown(i);
+	}
+
+	fn siftup(&mut self, start_index: usize) {
+		let mut current = start_index;
+		while current != 0 && self.has_priority_over(current, parent(current)) {
+			self.swap(current, parent(current));
+			current = parent(current);
+		}
+	}
+
+	fn siftdown(&mut self, start_index: usize) {
+		let mut index = start_index;
+		while !self.is_leaf(index) {
+			let left_ch = left_ch(index);
+			let right_ch = right_ch(index);
+
+			let top_ch_index = if right_ch < self.heap.len() && self.has_priority_over(right_ch, left_ch) {
+				right_ch
+			} else {
+				left_ch
+			};
+
+			if self.has_priority_over(index, top_ch_index) {
+				return;
+			}
+
+			self.swap(top_ch_index, index);
+			index = top_ch_index;
+		}
+	}
+
+	// Returns true if `self.heap[a]` should come out of the queue before
+	// `self.heap[b]`.
+	#[inline]
+	fn has_priority_over(&self, a: usize, b: usize) -> bool {
+		self.heap[a].1 > self.heap[b].1
+	}
+
+	#[inline]
+	fn is_leaf(&self, index: usize) -> bool {
+		index >= self.heap.len() / 2 && index < self.heap.len()
+	}
+
+	// Swaps two heap slots and keeps `index` pointing at their new
+	// positions so it stays a valid item -> heap-slot lookup.
+	fn swap(&mut self, a: usize, b: usize) {
+		self.heap.swap(a, b);
+		self.index.insert(self.heap[a].0.clone(), a);
+		self.index.insert(self.heap[b].0.clone(), b);
+	}
+}
+
+impl<Item, Priority> Default for IndexedPriorityQueue<Item, Priority>
+where
+	Item: Eq + Hash + Clone + Debug,
+	Priority: PartialOrd + Debug,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+fn parent(child: usize) -> usize {
+	(child - 1) / 2
+}
+
+fn right_ch(parent: usize) -> usize {
+	parent * 2 + 2
+}
+
+fn left_ch(parent: usize) -> usize {
+	parent * 2 + 1
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn indexed_priority_queue_creates_with_new_factory() {
+		let pq: IndexedPriorityQueue<&str, i32> = IndexedPriorityQueue::new();
+		assert!(pq.is_empty());
+	}
+
+	#[test]
+	fn indexed_priority_queue_pushes_and_peeks_highest_priority() {
+		let mut pq = IndexedPriorityQueue::new();
+		pq.push("a", 4);
+		pq.push("b", 9);
+		pq.push("c", 0);
+		assert_eq!(3, pq.len());
+		assert_eq!(Some((&"b", &9)), pq.peek());
+	}
+
+	#[test]
+	fn indexed_priority_queue_pops_in_priority_order() {
+		let mut pq = IndexedPriorityQueue::new();
+		pq.push("a", 4);
+		pq.push("b", 9);
+		pq.push("c", 0);
+		assert_eq!(Some(("b", 9)), pq.pop());
+		assert_eq!(Some(("a", 4)), pq.pop());
+		assert_eq!(Some(("c", 0)), pq.pop());
+		assert_eq!(None, pq.pop());
+	}
+
+	#[test]
+	fn indexed_priority_queue_push_updates_existing_item() {
+		let mut pq = IndexedPriorityQueue::new();
+		pq.push("a", 4);
+		pq.push("b", 9);
+		pq.push("a", 100);
+		assert_eq!(2, pq.len());
+		assert_eq!(Some((&"a", &100)), pq.peek());
+	}
+
+	#[test]
+	fn indexed_priority_queue_change_priority_decreases_and_increases_key() {
+		let mut pq = IndexedPriorityQueue::new();
+		pq.push("a", 4);
+		pq.push("b", 9);
+		pq.push("c", 0);
+
+		assert_eq!(Some(9), pq.change_priority(&"b", 1));
+		assert_eq!(Some((&"a", &4)), pq.peek());
+
+		assert_eq!(Some(0), pq.change_priority(&"c", 50));
+		assert_eq!(Some((&"c", &50)), pq.peek());
+	}
+
+	#[test]
+	fn indexed_priority_queue_change_priority_on_absent_item_returns_none() {
+		let mut pq: IndexedPriorityQueue<&str, i32> = IndexedPriorityQueue::new();
+		pq.push("a", 4);
+		assert_eq!(None, pq.change_priority(&"z", 1));
+	}
+
+	#[test]
+	fn indexed_priority_queue_get_priority() {
+		let mut pq = IndexedPriorityQueue::new();
+		pq.push("a", 4);
+		assert_eq!(Some(&4), pq.get_priority(&"a"));
+		assert_eq!(None, pq.get_priority(&"z"));
+	}
+}
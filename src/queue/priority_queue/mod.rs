@@ -1,5 +1,6 @@
 // MIT License Mayeul (Mike) Fournial <mayeul.fournial@outlook.com> - 2017
 
+use std::cmp::Ordering;
 use std::fmt::Debug;
 
 /// A priority queue implementation based on an unbounded max heap.
@@ -48,14 +49,37 @@ use std::fmt::Debug;
 /// It is possible to retrieve the priority queue as a slice. However it'll be
 /// in a heap order, not consecutive natural ordering of the elements.
 ///
-#[derive(Debug, PartialEq)]
-pub struct PriorityQueue<T: PartialOrd + PartialEq + Debug> {
+/// # `'static` requirement
+///
+/// `T` must be `'static`, since the comparator is stored as a boxed trait
+/// object (`Box<dyn Fn(&T, &T) -> Ordering>`) to support `min()` and
+/// `with_comparator`. This means a borrowed, non-`'static` element type such
+/// as `PriorityQueue<&'a str>` is not supported; clone or own the data first.
+///
+pub struct PriorityQueue<T: PartialOrd + PartialEq + Debug + 'static> {
 	heap: Vec<T>,
-	next_index: usize
+	next_index: usize,
+	comparator: Comparator<T>,
+}
+
+/// The ordering function behind a `PriorityQueue`: `Ordering::Greater` means
+/// its first argument should come out of the queue before its second.
+type Comparator<T> = Box<dyn Fn(&T, &T) -> Ordering>;
+
+/// The default comparator behind `PriorityQueue::new()`: a plain max-heap
+/// ordered by `PartialOrd`.
+fn max_cmp<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+	a.partial_cmp(b).expect("incomparable elements")
+}
+
+/// The comparator behind `PriorityQueue::min()`: a min-heap ordered by the
+/// reverse of `PartialOrd`.
+fn min_cmp<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+	b.partial_cmp(a).expect("incomparable elements")
 }
 
-impl<T: PartialOrd + PartialEq + Debug> PriorityQueue<T> {
-    /// Constructs a new, empty `PriorityQueue<T>`.
+impl<T: PartialOrd + PartialEq + Debug + 'static> PriorityQueue<T> {
+    /// Constructs a new, empty `PriorityQueue<T>`, ordered as a max-heap.
     ///
     /// # Examples
     ///
@@ -69,15 +93,65 @@ impl<T: PartialOrd + PartialEq + Debug> PriorityQueue<T> {
     /// ```
 	#[inline]
 	pub fn new() -> PriorityQueue<T> {
+		PriorityQueue::with_comparator(max_cmp)
+	}
+
+    /// Constructs a new, empty `PriorityQueue<T>`, ordered as a min-heap,
+    /// i.e. the *smallest* element is readable in `O(1)` time.
+    ///
+    /// This avoids having to wrap every element in a `std::cmp::Reverse` for
+    /// use cases such as shortest-path search, where the frontier should
+    /// always yield its cheapest entry first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate collections_more;
+    /// # use collections_more::queue::priority_queue::PriorityQueue;
+    /// # fn main() {
+    /// let mut pq = PriorityQueue::min();
+    /// pq.push(4);
+    /// pq.push(-55);
+    /// pq.push(9);
+    /// assert_eq!(Some(-55), pq.poll());
+    /// # }
+    /// ```
+	#[inline]
+	pub fn min() -> PriorityQueue<T> {
+		PriorityQueue::with_comparator(min_cmp)
+	}
+
+    /// Constructs a new, empty `PriorityQueue<T>` ordered by a custom
+    /// comparator `f`, where `f(a, b) == Ordering::Greater` means `a` should
+    /// come out of the queue before `b`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate collections_more;
+    /// # use collections_more::queue::priority_queue::PriorityQueue;
+    /// # fn main() {
+    /// // Orders by absolute value instead of natural order.
+    /// let mut pq = PriorityQueue::with_comparator(|a: &i32, b: &i32| a.abs().cmp(&b.abs()));
+    /// pq.push(-5);
+    /// pq.push(3);
+    /// assert_eq!(Some(-5), pq.poll());
+    /// # }
+    /// ```
+	pub fn with_comparator<F>(comparator: F) -> PriorityQueue<T>
+	where
+		F: Fn(&T, &T) -> Ordering + 'static,
+	{
 		PriorityQueue {
 			heap: Vec::new(),
 			next_index: 0,
+			comparator: Box::new(comparator),
 		}
 	}
 
     /// Constructs a new, empty `PriorityQueue<T>` with the specified capacity.
     ///
-    /// The priority queue will be able to hold exactly `capacity` 
+    /// The priority queue will be able to hold exactly `capacity`
     /// elements without reallocating.
     ///
     /// # Examples
@@ -105,6 +179,7 @@ impl<T: PartialOrd + PartialEq + Debug> PriorityQueue<T> {
         PriorityQueue {
             heap: Vec::with_capacity(capacity),
             next_index: 0,
+            comparator: Box::new(max_cmp),
         }
     }
 
@@ -121,7 +196,7 @@ impl<T: PartialOrd + PartialEq + Debug> PriorityQueue<T> {
     /// pq.push(2);
     /// pq.push(6);
     /// assert_eq!(2, pq.len());        // length of the pqueue
-    /// assert_eq!(Some(6), pq.poll()); // max element of pqueue
+    /// assert_eq!(Some(6), pq.poll()); // whichever element comparator ranks highest
     /// # }
     /// ```
 	pub fn push(&mut self, elem: T) {
@@ -129,7 +204,7 @@ impl<T: PartialOrd + PartialEq + Debug> PriorityQueue<T> {
 		self.heap.push(elem);
 		self.next_index += 1;
 
-		while current != 0 && self.heap[current] > self.heap[parent(current)] {
+		while current != 0 && self.has_priority_over(current, parent(current)) {
 			self.swap(current, parent(current));
 			current = parent(current);
 		}
@@ -180,7 +255,9 @@ impl<T: PartialOrd + PartialEq + Debug> PriorityQueue<T> {
 		self.heap.is_empty()
 	}
 
-    /// Returns a borrow to the biggest element of the queue (O(1) time).  
+    /// Returns a borrow to whichever element `comparator` ranks highest
+    /// (O(1) time) — the biggest element for the default max-heap, the
+    /// smallest for a queue built with `min()`.
     /// **returns `None` if queue is empty**
     ///
     /// # Example
@@ -202,8 +279,9 @@ impl<T: PartialOrd + PartialEq + Debug> PriorityQueue<T> {
 		Some(&self.heap[0])
 	}
 
-    /// Retrieves the biggest element of the queue, therefore deleting it from
-    /// the queue.  
+    /// Retrieves whichever element `comparator` ranks highest (the biggest
+    /// element for the default max-heap, the smallest for a queue built
+    /// with `min()`), therefore deleting it from the queue.
     /// **returns `None` if queue is empty**
     ///
     /// # Example
@@ -239,11 +317,89 @@ impl<T: PartialOrd + PartialEq + Debug> PriorityQueue<T> {
 		self.heap.as_slice()
 	}
 
+    /// Builds a `PriorityQueue<T>` from an existing `Vec<T>` in `O(n)` time,
+    /// using Floyd's bottom-up heap construction, rather than the `O(n log
+    /// n)` it would take to `push` every element one at a time.
+    ///
+    /// This always builds a max-heap ordered by `PartialOrd`; it does not
+    /// take a comparator. Use `with_comparator` (and then `push` each
+    /// element) if you need `min()`-style or custom ordering.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate collections_more;
+    /// # use collections_more::queue::priority_queue::PriorityQueue;
+    /// # fn main() {
+    /// let mut pq = PriorityQueue::from_vec(vec!(4, -55, 9, 0));
+    /// assert_eq!(4, pq.len());
+    /// assert_eq!(Some(9), pq.poll());
+    /// # }
+    /// ```
+	pub fn from_vec(items: Vec<T>) -> PriorityQueue<T> {
+		let next_index = items.len();
+		let mut pq = PriorityQueue {
+			heap: items,
+			next_index,
+			comparator: Box::new(max_cmp),
+		};
+
+		if next_index > 1 {
+			let mut index = next_index / 2;
+			loop {
+				pq.siftdown(index);
+				if index == 0 {
+					break;
+				}
+				index -= 1;
+			}
+		}
+
+		pq
+	}
+
+    /// Consumes the queue and returns its elements as a `Vec<T>`, using the
+    /// heap as an in-place heapsort: the root is repeatedly swapped with the
+    /// last unsorted slot and sifted back down.
+    ///
+    /// The result is sorted in the reverse order `poll()` would return
+    /// elements in, from the element the comparator ranks lowest to the one
+    /// it ranks highest, since the root is always whichever element the
+    /// comparator ranks highest: ascending for the default max-heap
+    /// comparator, descending for a queue built with `PriorityQueue::min()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate collections_more;
+    /// # use collections_more::queue::priority_queue::PriorityQueue;
+    /// # fn main() {
+    /// let pq = PriorityQueue::from_vec(vec!(4, -55, 9, 0));
+    /// assert_eq!(vec!(-55, 0, 4, 9), pq.into_sorted_vec());
+    /// # }
+    /// ```
+	pub fn into_sorted_vec(mut self) -> Vec<T> {
+		while self.next_index > 1 {
+			self.next_index -= 1;
+			self.heap.swap(0, self.next_index);
+			self.siftdown(0);
+		}
+
+		self.heap
+	}
+
     #[inline]
 	fn swap(&mut self, a: usize, b: usize) {
 		self.heap.swap(a, b)
 	}
 
+    /// Returns true if `self.heap[a]` should come out of the queue before
+    /// `self.heap[b]`, according to `comparator`.
+    #[inline]
+	fn has_priority_over(&self, a: usize, b: usize) -> bool {
+		(self.comparator)(&self.heap[a], &self.heap[b]) == Ordering::Greater
+	}
+
 	fn siftdown(&mut self, start_index: usize) {
 		let mut index = start_index;
 
@@ -251,18 +407,18 @@ impl<T: PartialOrd + PartialEq + Debug> PriorityQueue<T> {
 			let left_ch = left_ch(index);
 			let right_ch = right_ch(index);
 
-			let max_ch_index = if right_ch < self.next_index && self.heap[left_ch] < self.heap[right_ch] {
+			let top_ch_index = if right_ch < self.next_index && self.has_priority_over(right_ch, left_ch) {
 				right_ch
 			} else {
 				left_ch
 			};
 
-			if self.heap[max_ch_index] < self.heap[index] {
+			if self.has_priority_over(index, top_ch_index) {
 				return
 			}
 
-			self.swap(max_ch_index, index);
-			index = max_ch_index;
+			self.swap(top_ch_index, index);
+			index = top_ch_index;
 		}
 	}
 
@@ -283,7 +439,22 @@ fn left_ch(parent: usize) -> usize {
 	parent * 2 + 1
 }
 
-impl<T: PartialOrd + Debug> Iterator for PriorityQueue<T> {
+impl<T: PartialOrd + PartialEq + Debug + 'static> Debug for PriorityQueue<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.debug_struct("PriorityQueue")
+			.field("heap", &self.heap)
+			.field("next_index", &self.next_index)
+			.finish()
+	}
+}
+
+impl<T: PartialOrd + PartialEq + Debug + 'static> PartialEq for PriorityQueue<T> {
+	fn eq(&self, other: &PriorityQueue<T>) -> bool {
+		self.heap == other.heap && self.next_index == other.next_index
+	}
+}
+
+impl<T: PartialOrd + Debug + 'static> Iterator for PriorityQueue<T> {
 	type Item = T;
 
 	fn next(&mut self) -> Option<T> {
@@ -437,4 +608,65 @@ mod tests {
         }
         assert_eq!(expected, actual);
     }
+
+	#[test]
+	fn priority_queue_min_polls_smallest_first() {
+		let mut pq = PriorityQueue::min();
+		pq.push(4);
+		pq.push(-55);
+		pq.push(9);
+		pq.push(0);
+		assert_eq!(Some(-55), pq.poll());
+		assert_eq!(Some(0), pq.poll());
+		assert_eq!(Some(4), pq.poll());
+		assert_eq!(Some(9), pq.poll());
+	}
+
+	#[test]
+	fn priority_queue_with_custom_comparator_orders_by_absolute_value() {
+		let mut pq = PriorityQueue::with_comparator(|a: &i32, b: &i32| a.abs().cmp(&b.abs()));
+		pq.push(-5);
+		pq.push(3);
+		pq.push(-1);
+		pq.push(4);
+		assert_eq!(Some(-5), pq.poll());
+		assert_eq!(Some(4), pq.poll());
+		assert_eq!(Some(3), pq.poll());
+		assert_eq!(Some(-1), pq.poll());
+	}
+
+	#[test]
+	fn priority_queue_from_vec_builds_a_valid_heap() {
+		let mut pq = PriorityQueue::from_vec(vec!(1, 6, 2, 8, 4, 3, 2, 10, 7));
+		assert_eq!(9, pq.len());
+		assert_eq!(Some(10), pq.poll());
+		assert_eq!(Some(8), pq.poll());
+		assert_eq!(Some(7), pq.poll());
+	}
+
+	#[test]
+	fn priority_queue_from_vec_on_empty_and_singleton_vecs() {
+		let mut empty: PriorityQueue<i32> = PriorityQueue::from_vec(vec!());
+		assert!(empty.is_empty());
+		assert_eq!(None, empty.poll());
+
+		let mut single = PriorityQueue::from_vec(vec!(42));
+		assert_eq!(Some(42), single.poll());
+	}
+
+	#[test]
+	fn priority_queue_into_sorted_vec_is_ascending() {
+		let pq = PriorityQueue::from_vec(vec!(1, -2, 32, -4, 5, 6, -90));
+		assert_eq!(vec!(-90, -4, -2, 1, 5, 6, 32), pq.into_sorted_vec());
+	}
+
+	#[test]
+	fn priority_queue_into_sorted_vec_on_empty_and_singleton_vecs() {
+		let empty: PriorityQueue<i32> = PriorityQueue::new();
+		assert_eq!(Vec::<i32>::new(), empty.into_sorted_vec());
+
+		let mut single = PriorityQueue::new();
+		single.push(42);
+		assert_eq!(vec!(42), single.into_sorted_vec());
+	}
 }
\ No newline at end of file
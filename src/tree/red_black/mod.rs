@@ -1,14 +1,26 @@
 use std::fmt::Debug;
+use std::mem;
 
 use self::Colour::*;
 
+/// A left-leaning red-black binary search tree, as described by
+/// Robert Sedgewick. Every left-leaning 3-node is represented as a black
+/// node with a single red left child, which keeps the rebalancing logic to
+/// a handful of local rotations and colour flips instead of the eight-case
+/// fix-up a classic (non left-leaning) red-black tree requires.
+///
+/// Duplicate keys are supported: a node holds a `count` of how many times
+/// its key was inserted, rather than storing each occurrence as its own
+/// node. `size()` grows with every `insert`, even for an element already
+/// present, but the underlying tree always has exactly one node per
+/// distinct key, which keeps deletion unambiguous.
 #[derive(Debug, PartialEq)]
-pub struct RedBlackT<T: PartialOrd + PartialEq + Debug> {
-	root: Option<Node<T>>,
+pub struct RedBlackT<T: PartialOrd + PartialEq + Debug + Clone> {
+	root: Option<Box<Node<T>>>,
 	size: usize,
 }
 
-impl<T: PartialOrd + PartialEq + Debug> RedBlackT<T> {
+impl<T: PartialOrd + PartialEq + Debug + Clone> RedBlackT<T> {
 	pub fn new() -> RedBlackT<T> {
 		RedBlackT {
 			root: None,
@@ -16,23 +28,58 @@ impl<T: PartialOrd + PartialEq + Debug> RedBlackT<T> {
 		}
 	}
 
-	pub fn check(ref rb: &RedBlackT<T>) -> bool {
-		false
+	/// Verifies the red-black invariants hold: the root is black, no red
+	/// node has a red child, and every root-to-leaf path carries the same
+	/// number of black nodes.
+	pub fn check(rb: &RedBlackT<T>) -> bool {
+		if rb.root.as_ref().is_some_and(|root| root.colour != Black) {
+			return false;
+		}
+		Self::no_red_red(rb.root.as_deref()) && Self::black_height(rb.root.as_deref()).is_some()
 	}
 
 	pub fn insert(&mut self, elem: T) {
-		if self.root.is_none() {
-			self.root = Some(Node::new(elem, Black));
-		} else {
-			// self.root.//insert(elem);
+		self.root = Some(Self::insert_node(self.root.take(), elem));
+		if let Some(root) = self.root.as_mut() {
+			root.colour = Black;
 		}
+		self.size += 1;
 	}
 
 	pub fn remove(&mut self, elem: &T) -> Option<T> {
-		None	
+		if !self.contains(elem) {
+			return None;
+		}
+
+		let mut root = self.root.take()?;
+		// Sedgewick's delete precondition: the root must be red (or have a
+		// red child) before we start descending, so that `move_red_left`/
+		// `move_red_right` always have a red link to borrow from.
+		if !is_red(&root.left) && !is_red(&root.right) {
+			root.colour = Red;
+		}
+		let (new_root, removed) = Self::delete_node(root, elem);
+		self.root = new_root;
+		if let Some(root) = self.root.as_mut() {
+			root.colour = Black;
+		}
+		if removed.is_some() {
+			self.size -= 1;
+		}
+		removed
 	}
 
 	pub fn contains(&self, elem: &T) -> bool {
+		let mut current = self.root.as_deref();
+		while let Some(node) = current {
+			if *elem == node.elem {
+				return true;
+			} else if *elem < node.elem {
+				current = node.left.as_deref();
+			} else {
+				current = node.right.as_deref();
+			}
+		}
 		false
 	}
 
@@ -43,38 +90,254 @@ impl<T: PartialOrd + PartialEq + Debug> RedBlackT<T> {
 	pub fn is_empty(&self) -> bool {
 		self.size == 0
 	}
+
+	/// Returns the elements of the tree in sorted order, paired with their
+	/// rank (their index in that ordering).
+	pub fn enumerator(&self) -> std::vec::IntoIter<(&T, usize)> {
+		let mut elements = Vec::with_capacity(self.size);
+		Self::in_order(self.root.as_deref(), &mut elements);
+		elements
+			.into_iter()
+			.enumerate()
+			.map(|(index, elem)| (elem, index))
+			.collect::<Vec<_>>()
+			.into_iter()
+	}
+
+	fn in_order<'a>(node: Option<&'a Node<T>>, elements: &mut Vec<&'a T>) {
+		if let Some(node) = node {
+			Self::in_order(node.left.as_deref(), elements);
+			for _ in 0..node.count {
+				elements.push(&node.elem);
+			}
+			Self::in_order(node.right.as_deref(), elements);
+		}
+	}
+
+	fn insert_node(node: Option<Box<Node<T>>>, elem: T) -> Box<Node<T>> {
+		let mut node = match node {
+			None => return Box::new(Node::new(elem, Red)),
+			Some(node) => node,
+		};
+
+		if elem < node.elem {
+			node.left = Some(Self::insert_node(node.left.take(), elem));
+		} else if elem > node.elem {
+			node.right = Some(Self::insert_node(node.right.take(), elem));
+		} else {
+			node.count += 1;
+			return node;
+		}
+
+		Self::balance(node)
+	}
+
+	// Unlinks the minimum node of `node`'s subtree wholesale (key and
+	// count together) and returns it, for `delete_node` to splice into the
+	// spot of the node it is deleting. It never merges into an existing
+	// node, so a duplicate key is never represented by two physical nodes.
+	fn delete_min(mut node: Box<Node<T>>) -> (Option<Box<Node<T>>>, (T, usize)) {
+		if node.left.is_none() {
+			return (None, (node.elem, node.count));
+		}
+
+		if !is_red(&node.left) && !is_red(&node.left.as_ref().unwrap().left) {
+			node = Self::move_red_left(node);
+		}
+
+		let (new_left, min) = Self::delete_min(node.left.take().unwrap());
+		node.left = new_left;
+		(Some(Self::balance(node)), min)
+	}
+
+	fn delete_node(mut node: Box<Node<T>>, elem: &T) -> (Option<Box<Node<T>>>, Option<T>) {
+		let mut removed = None;
+
+		if *elem < node.elem {
+			if node.left.is_some() {
+				if !is_red(&node.left) && !is_red(&node.left.as_ref().unwrap().left) {
+					node = Self::move_red_left(node);
+				}
+				let (new_left, found) = Self::delete_node(node.left.take().unwrap(), elem);
+				node.left = new_left;
+				removed = found;
+			}
+		} else {
+			// Keys are unique per node (duplicates live in `count`), so
+			// once we find the node holding `elem` there is no other node
+			// in the tree that could also match it; a repeated key just
+			// loses one occurrence in place, with no rebalancing needed.
+			if *elem == node.elem && node.count > 1 {
+				node.count -= 1;
+				let removed_elem = node.elem.clone();
+				return (Some(node), Some(removed_elem));
+			}
+			if is_red(&node.left) {
+				node = Self::rotate_right(node);
+			}
+			if *elem == node.elem && node.right.is_none() {
+				return (None, Some(node.elem));
+			}
+			if node.right.is_some() {
+				if !is_red(&node.right) && !is_red(&node.right.as_ref().unwrap().left) {
+					node = Self::move_red_right(node);
+				}
+				if *elem == node.elem {
+					let (new_right, (min_elem, min_count)) = Self::delete_min(node.right.take().unwrap());
+					let old_elem = mem::replace(&mut node.elem, min_elem);
+					node.count = min_count;
+					node.right = new_right;
+					removed = Some(old_elem);
+				} else {
+					let (new_right, found) = Self::delete_node(node.right.take().unwrap(), elem);
+					node.right = new_right;
+					removed = found;
+				}
+			}
+		}
+
+		(Some(Self::balance(node)), removed)
+	}
+
+	fn move_red_left(mut node: Box<Node<T>>) -> Box<Node<T>> {
+		Self::flip_colours(&mut node);
+		if is_red(&node.right.as_ref().unwrap().left) {
+			node.right = Some(Self::rotate_right(node.right.take().unwrap()));
+			node = Self::rotate_left(node);
+			Self::flip_colours(&mut node);
+		}
+		node
+	}
+
+	fn move_red_right(mut node: Box<Node<T>>) -> Box<Node<T>> {
+		Self::flip_colours(&mut node);
+		if is_red(&node.left.as_ref().unwrap().left) {
+			node = Self::rotate_right(node);
+			Self::flip_colours(&mut node);
+		}
+		node
+	}
+
+	fn balance(mut node: Box<Node<T>>) -> Box<Node<T>> {
+		if is_red(&node.right) && !is_red(&node.left) {
+			node = Self::rotate_left(node);
+		}
+		if is_red(&node.left) && is_red(&node.left.as_ref().unwrap().left) {
+			node = Self::rotate_right(node);
+		}
+		if is_red(&node.left) && is_red(&node.right) {
+			Self::flip_colours(&mut node);
+		}
+		node
+	}
+
+	fn rotate_left(mut node: Box<Node<T>>) -> Box<Node<T>> {
+		let mut right = node.right.take().expect("rotate_left requires a right child");
+		node.right = right.left.take();
+		right.colour = node.colour;
+		node.colour = Red;
+		right.left = Some(node);
+		right
+	}
+
+	fn rotate_right(mut node: Box<Node<T>>) -> Box<Node<T>> {
+		let mut left = node.left.take().expect("rotate_right requires a left child");
+		node.left = left.right.take();
+		left.colour = node.colour;
+		node.colour = Red;
+		left.right = Some(node);
+		left
+	}
+
+	fn flip_colours(node: &mut Node<T>) {
+		node.colour = !node.colour;
+		if let Some(left) = node.left.as_mut() {
+			left.colour = !left.colour;
+		}
+		if let Some(right) = node.right.as_mut() {
+			right.colour = !right.colour;
+		}
+	}
+
+	fn no_red_red(node: Option<&Node<T>>) -> bool {
+		match node {
+			None => true,
+			Some(node) => {
+				if node.colour == Red && (is_red(&node.left) || is_red(&node.right)) {
+					return false;
+				}
+				Self::no_red_red(node.left.as_deref()) && Self::no_red_red(node.right.as_deref())
+			}
+		}
+	}
+
+	fn black_height(node: Option<&Node<T>>) -> Option<usize> {
+		match node {
+			None => Some(1),
+			Some(node) => {
+				let left = Self::black_height(node.left.as_deref())?;
+				let right = Self::black_height(node.right.as_deref())?;
+				if left != right {
+					return None;
+				}
+				Some(if node.colour == Black { left + 1 } else { left })
+			}
+		}
+	}
+}
+
+fn is_red<T: PartialOrd + PartialEq + Debug + Clone>(node: &Option<Box<Node<T>>>) -> bool {
+	match node {
+		Some(node) => node.colour == Red,
+		None => false,
+	}
 }
 
 #[derive(Debug)]
-struct Node<T: PartialOrd + PartialEq + Debug> {
+struct Node<T: PartialOrd + PartialEq + Debug + Clone> {
 	elem: T,
-	colour: Colour
+	// How many times `elem` was inserted; duplicates are folded into this
+	// counter instead of being stored as their own node.
+	count: usize,
+	colour: Colour,
+	left: Option<Box<Node<T>>>,
+	right: Option<Box<Node<T>>>,
 }
 
-impl<T: PartialOrd + PartialEq + Debug> Node<T> {
+impl<T: PartialOrd + PartialEq + Debug + Clone> Node<T> {
 	fn new(elem: T, colour: Colour) -> Node<T> {
-		Node{ elem, colour }
-	}
-
-	fn insert(&mut self, elem: T) {
-
+		Node { elem, count: 1, colour, left: None, right: None }
 	}
 }
 
 impl<T> PartialEq for Node<T>
-    where T: PartialOrd + PartialEq + Debug
+    where T: PartialOrd + PartialEq + Debug + Clone
 {
 	fn eq(&self, other: &Node<T>) -> bool {
-		return self.elem== other.elem
+		self.elem == other.elem
+			&& self.count == other.count
+			&& self.left == other.left
+			&& self.right == other.right
 	}
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 enum Colour {
     Red,
     Black,
 }
 
+impl std::ops::Not for Colour {
+	type Output = Colour;
+
+	fn not(self) -> Colour {
+		match self {
+			Red => Black,
+			Black => Red,
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -152,12 +415,66 @@ mod tests {
 		rb.insert(53);
 		rb.insert(53);
 		rb.insert(533);
-		RedBlackT::check(&rb);
+		assert!(RedBlackT::check(&rb));
 		rb.remove(&522);
 		rb.remove(&-40);
 		rb.remove(&53);
 		rb.remove(&0);
-		RedBlackT::check(&rb);
+		assert!(RedBlackT::check(&rb));
+	}
+
+	#[test]
+	fn red_black_remove_one_duplicate_keeps_the_other() {
+		let mut rb: RedBlackT<i32> = RedBlackT::new();
+		rb.insert(5);
+		rb.insert(5);
+		assert_eq!(Some(5), rb.remove(&5));
+		assert_eq!(1, rb.size());
+		assert!(rb.contains(&5));
+		assert!(RedBlackT::check(&rb));
+		assert_eq!(Some(5), rb.remove(&5));
+		assert_eq!(0, rb.size());
+		assert!(!rb.contains(&5));
+	}
+
+	#[test]
+	fn red_black_remove_keeps_size_and_enumerator_in_sync_with_duplicates() {
+		// Regression test for a Hibbard-deletion bug where removing a key
+		// whose in-order successor had duplicates would lose an unrelated
+		// element: `size()` kept counting it while `enumerator()` did not.
+		let mut rb: RedBlackT<i32> = RedBlackT::new();
+		for elem in [1, 10, 10, 3, 3, 0, 2, 9, 8] {
+			rb.insert(elem);
+		}
+		rb.remove(&3);
+		rb.insert(8);
+		rb.insert(6);
+
+		rb.remove(&8);
+		assert!(RedBlackT::check(&rb));
+		assert_eq!(rb.size(), rb.enumerator().count());
+
+		rb.remove(&3);
+		assert!(RedBlackT::check(&rb));
+		assert_eq!(rb.size(), rb.enumerator().count());
+	}
+
+	#[test]
+	fn red_black_remove_keeps_multiset_intact_across_duplicate_successors() {
+		// Regression test: removing a key whose in-order successor subtree
+		// itself contains duplicates must not drop an occurrence of another
+		// key, and size()/enumerator()/check() must all agree afterwards.
+		let mut rb: RedBlackT<i32> = RedBlackT::new();
+		for elem in [1, 10, 8, 10, 10, 10, 11, 11, 11] {
+			rb.insert(elem);
+		}
+		rb.remove(&11);
+		rb.insert(11);
+		rb.insert(10);
+		rb.remove(&11);
+
+		assert!(RedBlackT::check(&rb));
+		assert_eq!(rb.size(), rb.enumerator().count());
 	}
 
 	#[test]
@@ -176,11 +493,11 @@ mod tests {
 		rb.insert(53);
 		rb.insert(53);
 		rb.insert(533);
-		
+
 		let expected = vec![-40, -25, 0, 1, 3, 5, 5, 53, 53, 81, 522, 533];
 
 		for (elem, index) in rb.enumerator() {
-			assert_eq!(expected.peek(index), elem);
+			assert_eq!(&expected[index], elem);
 		}
 	}
 }